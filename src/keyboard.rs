@@ -0,0 +1,56 @@
+use spin::Mutex;
+
+const QUEUE_CAPACITY: usize = 100;
+
+/// Fixed-capacity ring buffer of decoded keypresses, filled by the keyboard
+/// interrupt handler and drained by whatever in the kernel wants to read input.
+/// Interrupts are enabled before the heap is initialized, so this can't just
+/// be a `VecDeque`.
+struct CharQueue {
+    buf: [char; QUEUE_CAPACITY],
+    head: usize,
+    len: usize,
+}
+
+impl CharQueue {
+    const fn new() -> Self {
+        CharQueue {
+            buf: ['\0'; QUEUE_CAPACITY],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        if self.len == QUEUE_CAPACITY {
+            // drop the oldest character to make room
+            self.head = (self.head + 1) % QUEUE_CAPACITY;
+            self.len -= 1;
+        }
+        let tail = (self.head + self.len) % QUEUE_CAPACITY;
+        self.buf[tail] = c;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<char> {
+        if self.len == 0 {
+            return None;
+        }
+        let c = self.buf[self.head];
+        self.head = (self.head + 1) % QUEUE_CAPACITY;
+        self.len -= 1;
+        Some(c)
+    }
+}
+
+static QUEUE: Mutex<CharQueue> = Mutex::new(CharQueue::new());
+
+/// Called from the keyboard interrupt handler with each decoded character.
+pub(crate) fn push(c: char) {
+    QUEUE.lock().push(c);
+}
+
+/// Pops the oldest decoded character the kernel hasn't consumed yet, if any.
+pub fn pop() -> Option<char> {
+    QUEUE.lock().pop()
+}
@@ -4,13 +4,32 @@
 #![test_runner(rustferret::test_runner)]
 #![reexport_test_harness_main = "test_main"]
 
+extern crate alloc;
+
+use alloc::boxed::Box;
+use bootloader::{entry_point, BootInfo};
 use core::panic::PanicInfo;
 use rustferret::println;
 
-#[unsafe(no_mangle)]
-pub extern "C" fn _start() -> ! {
+entry_point!(kernel_main);
+
+fn kernel_main(boot_info: &'static BootInfo) -> ! {
+    use rustferret::allocator;
+    use rustferret::memory::{self, BootInfoFrameAllocator};
+    use x86_64::VirtAddr;
+
     println!("Hello World{}", "!");
     rustferret::init();
+
+    let phys_mem_offset = VirtAddr::new(boot_info.physical_memory_offset);
+    let mut mapper = unsafe { memory::init(phys_mem_offset) };
+    let mut frame_allocator = unsafe { BootInfoFrameAllocator::init(&boot_info.memory_map) };
+
+    allocator::init_heap(&mut mapper, &mut frame_allocator).expect("heap initialization failed");
+
+    let heap_value = Box::new(41);
+    println!("heap_value at {:p}", heap_value);
+
     x86_64::instructions::interrupts::int3();
 
     #[cfg(test)]
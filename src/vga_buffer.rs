@@ -1,5 +1,6 @@
 
 use volatile::Volatile;
+use x86_64::instructions::port::Port;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -23,6 +24,38 @@ pub enum Color {
     White = 15,
 }
 
+fn color_from_code(code: u8) -> Option<Color> {
+    Some(match code {
+        0 => Color::Black,
+        1 => Color::Blue,
+        2 => Color::Green,
+        3 => Color::Cyan,
+        4 => Color::Red,
+        5 => Color::Magenta,
+        6 => Color::Brown,
+        7 => Color::LightGray,
+        8 => Color::DarkGray,
+        9 => Color::LightBlue,
+        10 => Color::LightGreen,
+        11 => Color::LightCyan,
+        12 => Color::LightRed,
+        13 => Color::Pink,
+        14 => Color::Yellow,
+        15 => Color::White,
+        _ => return None,
+    })
+}
+
+/// Rejects any parsed escape parameter outside the valid `Color` range
+/// instead of truncating it, so e.g. `\x1b[256m` falls back cleanly.
+fn u16_to_color_code_byte(v: u16) -> Option<u8> {
+    if v <= 15 {
+        Some(v as u8)
+    } else {
+        None
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 struct ColorCode(u8);
@@ -50,9 +83,20 @@ struct Buffer {
     chars: [[Volatile<ScreenChar>; BUFFER_WIDTH]; BUFFER_HEIGHT],
 }
 
+// Escape-sequence parser state for the compact in-band color syntax
+// `\x1b[<fg>m` or `\x1b[<fg>;<bg>m`, where <fg>/<bg> are the numeric
+// discriminants of `Color` (0-15).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EscapeState {
+    Normal,
+    Escape,
+    Params { fg: Option<u16>, cur: Option<u16> },
+}
+
 pub struct Writer {
     column_position: usize,
     current_color: ColorCode,
+    escape_state: EscapeState,
     buffer: &'static mut Buffer,
 }
 
@@ -71,10 +115,58 @@ impl Writer{
                 self.column_position += 1;
             }
         }
+        self.update_cursor();
+    }
+
+    /// Programs the CRT controller so the blinking hardware cursor follows
+    /// the last character written, instead of staying pinned at (0, 0).
+    fn update_cursor(&self) {
+        let pos = (BUFFER_HEIGHT - 1) * BUFFER_WIDTH + self.column_position;
+        unsafe {
+            let mut index_port: Port<u8> = Port::new(0x3D4);
+            let mut data_port: Port<u8> = Port::new(0x3D5);
+
+            index_port.write(0x0F);
+            data_port.write((pos & 0xFF) as u8);
+            index_port.write(0x0E);
+            data_port.write(((pos >> 8) & 0xFF) as u8);
+        }
+    }
+
+    /// Enables the hardware text-mode cursor with the given start/end scanlines.
+    pub fn enable_cursor(start_scanline: u8, end_scanline: u8) {
+        unsafe {
+            let mut index_port: Port<u8> = Port::new(0x3D4);
+            let mut data_port: Port<u8> = Port::new(0x3D5);
+
+            index_port.write(0x0A);
+            let current = data_port.read();
+            index_port.write(0x0A);
+            data_port.write((current & 0xC0) | start_scanline);
+
+            index_port.write(0x0B);
+            let current = data_port.read();
+            index_port.write(0x0B);
+            data_port.write((current & 0xE0) | end_scanline);
+        }
+    }
+
+    /// Disables the hardware text-mode cursor.
+    pub fn disable_cursor() {
+        unsafe {
+            let mut index_port: Port<u8> = Port::new(0x3D4);
+            let mut data_port: Port<u8> = Port::new(0x3D5);
+
+            index_port.write(0x0A);
+            data_port.write(0x20);
+        }
     }
 
     pub fn write_string(&mut self, s: &str){
         for byte in s.bytes(){
+            if self.step_escape(byte) {
+                continue;
+            }
             match byte {
                 0x20..=0x7e | b'\n' => self.write_byte(byte),
                 _ => self.write_byte(0xfe),
@@ -82,6 +174,73 @@ impl Writer{
         }
     }
 
+    /// Feeds a byte through the color escape-sequence state machine.
+    /// Returns `true` if the byte was consumed by the sequence (i.e. should
+    /// not also be written to the screen).
+    fn step_escape(&mut self, byte: u8) -> bool {
+        match self.escape_state {
+            EscapeState::Normal => {
+                if byte == 0x1b {
+                    self.escape_state = EscapeState::Escape;
+                    true
+                } else {
+                    false
+                }
+            }
+            EscapeState::Escape => {
+                if byte == b'[' {
+                    self.escape_state = EscapeState::Params { fg: None, cur: None };
+                } else {
+                    // not a recognized sequence, give up and flag it
+                    self.escape_state = EscapeState::Normal;
+                    self.write_byte(0xfe);
+                }
+                true
+            }
+            EscapeState::Params { fg, cur } => {
+                match byte {
+                    b'0'..=b'9' => {
+                        let digit = (byte - b'0') as u16;
+                        // Saturate instead of panicking on overflow (dev profile
+                        // has overflow checks on) -- anything this large is an
+                        // invalid color code anyway and gets rejected below.
+                        let cur = Some(cur.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+                        self.escape_state = EscapeState::Params { fg, cur };
+                    }
+                    b';' if fg.is_none() => {
+                        self.escape_state = EscapeState::Params { fg: cur, cur: None };
+                    }
+                    b'm' => {
+                        self.escape_state = EscapeState::Normal;
+                        // A lone `\x1b[<n>m` has no `;`, so the accumulated
+                        // number is still sitting in `cur` and `fg` is None --
+                        // that number is the foreground code, not background.
+                        let (fg, bg) = match fg {
+                            Some(fg) => (Some(fg), cur),
+                            None => (cur, None),
+                        };
+                        self.apply_color_code(fg, bg);
+                    }
+                    _ => {
+                        self.escape_state = EscapeState::Normal;
+                        self.write_byte(0xfe);
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn apply_color_code(&mut self, fg: Option<u16>, bg: Option<u16>) {
+        let bg = bg.unwrap_or((self.current_color.0 >> 4) as u16);
+        let fg = fg.and_then(u16_to_color_code_byte).and_then(color_from_code);
+        let bg = u16_to_color_code_byte(bg).and_then(color_from_code);
+        match (fg, bg) {
+            (Some(fg), Some(bg)) => self.current_color = ColorCode::new(fg, bg),
+            _ => self.write_byte(0xfe),
+        }
+    }
+
     fn new_line(&mut self){
         //move all lines up
         for row in 1..BUFFER_HEIGHT {
@@ -92,6 +251,7 @@ impl Writer{
         }
         self.clear_row(BUFFER_HEIGHT-1);
         self.column_position = 0;
+        self.update_cursor();
     }
     fn clear_row(&mut self, row: usize){
         for col in 0..BUFFER_WIDTH {
@@ -102,6 +262,14 @@ impl Writer{
             self.buffer.chars[row][col].write(blank);
         }
     }
+
+    pub fn set_color(&mut self, fg: Color, bg: Color) {
+        self.current_color = ColorCode::new(fg, bg);
+    }
+
+    pub fn reset_color(&mut self) {
+        self.current_color = ColorCode::new(Color::Yellow, Color::Black);
+    }
 }
 
 
@@ -122,6 +290,7 @@ pub static ref WRITER: Mutex<Writer> = Mutex::new(
         Writer {
     column_position: 0,
     current_color: ColorCode::new(Color::Yellow, Color::Black),
+    escape_state: EscapeState::Normal,
     buffer: unsafe { &mut *(VGAPOINTER as *mut Buffer)},
 }
     );
@@ -141,10 +310,32 @@ macro_rules! println {
     ($($arg:tt)*) => ($crate::print!("{}\n", format_args!($($arg)*)));
 }
 
+/// Prints a line using the given foreground/background `Color`s, then
+/// resets the writer to the default color (yellow on black); a color set
+/// before the call is not kept.
+#[macro_export]
+macro_rules! color_println {
+    ($fg:expr, $bg:expr, $($arg:tt)*) => {{
+        let (fg, bg) = ($fg, $bg);
+        ::x86_64::instructions::interrupts::without_interrupts(|| {
+            let mut writer = $crate::vga_buffer::WRITER.lock();
+            writer.set_color(fg, bg);
+            ::core::fmt::Write::write_fmt(&mut *writer, format_args!("{}\n", format_args!($($arg)*))).unwrap();
+            writer.reset_color();
+        });
+    }};
+}
+
 #[doc(hidden)]
 pub fn _print(args: fmt::Arguments) {
     use core::fmt::Write;
-    WRITER.lock().write_fmt(args).unwrap();
+    use x86_64::instructions::interrupts::without_interrupts;
+
+    // If a timer/keyboard interrupt fires while WRITER is locked and its handler
+    // also prints, it would deadlock spinning for a lock the interrupted code holds.
+    without_interrupts(|| {
+        WRITER.lock().write_fmt(args).unwrap();
+    });
 }
 
 
@@ -171,6 +362,54 @@ fn test_println_output() {
     }
 }
 
+#[test_case]
+fn test_color_escape_sequence() {
+    let mut writer = WRITER.lock();
+    writer.reset_color();
+    writer.write_string("\x1b[4mred text\n");
+    assert_eq!(writer.current_color, ColorCode::new(Color::Red, Color::Black));
+    writer.reset_color();
+}
+
+#[test_case]
+fn test_color_escape_sequence_malformed() {
+    let mut writer = WRITER.lock();
+    writer.reset_color();
+    let before = writer.current_color;
+    writer.write_string("\x1b[zzm\n");
+    assert_eq!(writer.current_color, before);
+}
+
+#[test_case]
+fn test_color_escape_sequence_out_of_range() {
+    let mut writer = WRITER.lock();
+    writer.reset_color();
+    let before = writer.current_color;
+    // 256 overflows a u8 if accumulated naively; must be rejected, not panic.
+    writer.write_string("\x1b[256m\n");
+    assert_eq!(writer.current_color, before);
+}
+
+#[test_case]
+fn test_set_color() {
+    let mut writer = WRITER.lock();
+    writer.set_color(Color::Green, Color::Blue);
+    assert_eq!(writer.current_color, ColorCode::new(Color::Green, Color::Blue));
+    writer.reset_color();
+}
+
+#[test_case]
+fn test_color_println() {
+    WRITER.lock().reset_color();
+    // color_println! sets the color for its own line then restores it, so the
+    // writer's color should be back to the default once the macro returns.
+    color_println!(Color::Green, Color::Blue, "color_println output");
+    assert_eq!(
+        WRITER.lock().current_color,
+        ColorCode::new(Color::Yellow, Color::Black)
+    );
+}
+
 #[test_case]
 fn test_println_wraparound(){
     println!("Long string! Gray eel-catfish labyrinth fish x-ray tetra, barbeled houndshark gianttail dorado Mexican golden trout, mudfish ground shark.\" North American freshwater catfish scaleless black dragonfish, \"blacktip reef shark,\" kaluga sea lamprey sixgill shark searobin; bluntnose knifefish, soldierfish. Butterfly ray red velvetfish golden trout humuhumunukunukuapua'a. Goldfish yellow-and-black triplefin mummichog, Pacific hake mackerel shark char banded killifish, \"scat halosaur, snoek weever, garden eel snailfish Pacific cod.\" Ghost fish roosterfish peamouth Australasian salmon jewel tetra pufferfish orbicular batfish convict cichlid stonecat spinefoot, seamoth silverside longjaw mudsucker burma danio shiner eucla cod yellowfin pike Asiatic glassfish. Javelin Pacific saury glowlight danio skipping goby jewelfish, hardhead catfish blackchin sand knifefish rivuline; Old World rivuline Atlantic trout.");
@@ -0,0 +1,64 @@
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(rustferret::test_runner)]
+#![reexport_test_harness_main = "test_main"]
+
+use core::panic::PanicInfo;
+
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    rustferret::gdt::init();
+    init_test_idt();
+
+    test_main();
+
+    loop {}
+}
+
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    rustferret::test_panic_handler(info)
+}
+
+use lazy_static::lazy_static;
+use x86_64::structures::idt::{InterruptDescriptorTable, InterruptStackFrame};
+
+lazy_static! {
+    static ref TEST_IDT: InterruptDescriptorTable = {
+        let mut idt = InterruptDescriptorTable::new();
+        unsafe {
+            idt.double_fault
+                .set_handler_fn(test_double_fault_handler)
+                .set_stack_index(rustferret::gdt::DOUBLE_FAULT_IST_INDEX);
+        }
+        idt
+    };
+}
+
+fn init_test_idt() {
+    TEST_IDT.load();
+}
+
+// If the IST stack is set up correctly the recursive overflow below triggers
+// this handler instead of a triple fault, so reaching it means the test passed.
+extern "x86-interrupt" fn test_double_fault_handler(
+    _stack_frame: InterruptStackFrame,
+    _error_code: u64,
+) -> ! {
+    rustferret::serial_println!("[ok]");
+    rustferret::exit_qemu(rustferret::QemuExitCode::Success);
+    loop {}
+}
+
+#[allow(unconditional_recursion)]
+fn stack_overflow() {
+    stack_overflow();
+    // prevent tail-call optimization from turning this into a loop
+    volatile::Volatile::new(0).read();
+}
+
+#[test_case]
+fn test_stack_overflow() {
+    stack_overflow();
+}